@@ -0,0 +1,86 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+pub mod xero;
+
+/// A ledger/accounting backend that can be reconciled against cash events
+/// from a [`crate::pos::PosSource`].
+#[async_trait]
+pub trait Ledger {
+    /// Fetch the invoices a cash event could plausibly be paying off.
+    async fn candidate_invoices(&self) -> Result<Vec<Invoice>>;
+
+    /// Submit a batch of payments against previously fetched invoices.
+    ///
+    /// `idempotency_key` should be stable across retries of the same batch
+    /// (derived from the ids of the POS events being submitted) so a
+    /// re-run over an overlapping window can't double-submit.
+    async fn submit_payments(&self, idempotency_key: &str, payments: &[PaymentRequest]) -> Result<()>;
+
+    /// Whether the ledger already considers this invoice paid.
+    fn is_already_paid(&self, invoice: &Invoice) -> bool {
+        invoice.amount_paid > Decimal::ZERO
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    pub invoice_type: InvoiceType,
+    pub invoice_id: String,
+    pub invoice_number: String,
+    pub amount_due: Decimal,
+    pub amount_paid: Decimal,
+    pub contact: Contact,
+    pub date: NaiveDateTime,
+    pub due_date: NaiveDateTime,
+    pub status: InvoiceStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceType {
+    AccPay,
+    AccRec,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceStatus {
+    Draft,
+    Submitted,
+    Authorised,
+    Paid,
+    Deleted,
+    Voided,
+}
+
+#[derive(Debug, Clone)]
+pub struct Contact {
+    pub contact_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub enum InvoiceMatchResult {
+    #[default]
+    None,
+    AlreadyPaid,
+    UnpaidSingle(Invoice),
+    UnpaidMultiple(Vec<Invoice>),
+    /// A refund event that reverses a payment already reconciled, either
+    /// earlier in this run or in a previous run.
+    RefundForReconciledPayment(Invoice),
+    /// A refund event matched directly against a receivable invoice, with
+    /// no prior reconciled payment found for it.
+    RefundForInvoice(Invoice),
+}
+
+/// A ledger-agnostic request to apply a payment to an invoice.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PaymentRequest {
+    pub invoice_id: String,
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub reference: String,
+}
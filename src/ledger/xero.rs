@@ -0,0 +1,292 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::NaiveDateTime;
+use reqwest::{
+    Client,
+    header::{AUTHORIZATION, HeaderMap, HeaderValue},
+};
+use reqwest_middleware::ClientWithMiddleware;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::http::http_client;
+
+use super::{Contact, Invoice, InvoiceStatus, InvoiceType, Ledger, PaymentRequest};
+
+pub struct XeroLedger {
+    client: ClientWithMiddleware,
+    payment_account_code: String,
+    max_pages: usize,
+}
+
+impl XeroLedger {
+    pub async fn new(
+        client_id: &str,
+        client_secret: &str,
+        tenant_id: &str,
+        payment_account_code: String,
+        max_pages: usize,
+    ) -> Result<Self> {
+        let access_token = get_xero_access_token(client_id, client_secret).await?;
+
+        let mut headers = HeaderMap::new();
+
+        let mut auth_value = HeaderValue::from_str(&format!("Bearer {access_token}")).unwrap();
+        auth_value.set_sensitive(true);
+
+        headers.insert(AUTHORIZATION, auth_value);
+        headers.insert("Accept", "application/json".parse().unwrap());
+        headers.insert("Xero-Tenant-Id", tenant_id.parse().unwrap());
+
+        Ok(Self {
+            client: http_client(headers),
+            payment_account_code,
+            max_pages,
+        })
+    }
+}
+
+const INVOICES_PAGE_SIZE: usize = 1000;
+
+#[async_trait]
+impl Ledger for XeroLedger {
+    async fn candidate_invoices(&self) -> Result<Vec<Invoice>> {
+        let mut invoices = Vec::new();
+
+        for page in 1..=self.max_pages {
+            let query = vec![
+                ("Statuses", "AUTHORISED,PAID".to_string()),
+                ("pageSize", INVOICES_PAGE_SIZE.to_string()),
+                ("page", page.to_string()),
+            ];
+
+            let response: GetInvoicesResponse = self
+                .client
+                .get("https://api.xero.com/api.xro/2.0/Invoices")
+                .query(&query)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            let page_len = response.invoices.len();
+            invoices.extend(response.invoices);
+
+            if page_len < INVOICES_PAGE_SIZE {
+                break;
+            }
+
+            if page == self.max_pages {
+                println!(
+                    "   Warning: reached --max-pages ({}) while fetching invoices; results may be incomplete",
+                    self.max_pages
+                );
+            }
+        }
+
+        Ok(invoices.into_iter().map(Invoice::from).collect())
+    }
+
+    async fn submit_payments(&self, idempotency_key: &str, payments: &[PaymentRequest]) -> Result<()> {
+        let payment_objects = payments
+            .iter()
+            .map(|p| PaymentRequestObject {
+                invoice: PaymentRequestObjectInvoice {
+                    invoice_id: p.invoice_id.clone(),
+                },
+                account: PaymentRequestObjectAccount {
+                    code: self.payment_account_code.clone(),
+                },
+                date: p.date,
+                amount: p.amount,
+                reference: p.reference.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        self.client
+            .put("https://api.xero.com/api.xro/2.0/Payments")
+            .header("Idempotency-Key", idempotency_key)
+            .json(&json!({
+                "Payments": payment_objects,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+async fn get_xero_access_token(client_id: &str, client_secret: &str) -> Result<String> {
+    let mut headers = HeaderMap::new();
+
+    let mut auth_value = HeaderValue::from_str(&format!(
+        "Basic {}",
+        BASE64_STANDARD.encode(format!("{client_id}:{client_secret}"))
+    ))
+    .unwrap();
+
+    auth_value.set_sensitive(true);
+    headers.insert(reqwest::header::AUTHORIZATION, auth_value);
+
+    let scopes = [
+        "accounting.transactions",
+        "accounting.transactions.read",
+        "accounting.reports.read",
+        "accounting.reports.tenninetynine.read",
+        "accounting.budgets.read",
+        "accounting.journals.read",
+        "accounting.settings",
+        "accounting.settings.read",
+        "accounting.contacts",
+        "accounting.attachments",
+        "accounting.contacts.read",
+        "accounting.attachments.read",
+    ]
+    .join(" ");
+
+    headers.insert(
+        "Content-Type",
+        "application/x-www-form-urlencoded".parse().unwrap(),
+    );
+
+    let client = Client::builder().default_headers(headers).build()?;
+
+    let response: Value = client
+        .post("https://identity.xero.com/connect/token")
+        .form(&[("grant_type", "client_credentials"), ("scope", &scopes)])
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    Ok(response
+        .pointer("/access_token")
+        .unwrap()
+        .as_str()
+        .unwrap()
+        .to_string())
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetInvoicesResponse {
+    invoices: Vec<XeroInvoice>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XeroInvoice {
+    #[serde(rename = "Type")]
+    invoice_type: XeroInvoiceType,
+    #[serde(rename = "InvoiceID")]
+    invoice_id: String,
+    invoice_number: String,
+    amount_due: Decimal,
+    amount_paid: Decimal,
+    contact: XeroContact,
+    #[serde(rename = "DateString")]
+    date: NaiveDateTime,
+    #[serde(rename = "DueDateString")]
+    due_date: NaiveDateTime,
+    status: XeroInvoiceStatus,
+}
+
+impl From<XeroInvoice> for Invoice {
+    fn from(value: XeroInvoice) -> Self {
+        Self {
+            invoice_type: value.invoice_type.into(),
+            invoice_id: value.invoice_id,
+            invoice_number: value.invoice_number,
+            amount_due: value.amount_due,
+            amount_paid: value.amount_paid,
+            contact: value.contact.into(),
+            date: value.date,
+            due_date: value.due_date,
+            status: value.status.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+enum XeroInvoiceType {
+    AccPay,
+    AccRec,
+}
+
+impl From<XeroInvoiceType> for InvoiceType {
+    fn from(value: XeroInvoiceType) -> Self {
+        match value {
+            XeroInvoiceType::AccPay => InvoiceType::AccPay,
+            XeroInvoiceType::AccRec => InvoiceType::AccRec,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum XeroInvoiceStatus {
+    Draft,
+    Submitted,
+    Authorised,
+    Paid,
+    Deleted,
+    Voided,
+}
+
+impl From<XeroInvoiceStatus> for InvoiceStatus {
+    fn from(value: XeroInvoiceStatus) -> Self {
+        match value {
+            XeroInvoiceStatus::Draft => InvoiceStatus::Draft,
+            XeroInvoiceStatus::Submitted => InvoiceStatus::Submitted,
+            XeroInvoiceStatus::Authorised => InvoiceStatus::Authorised,
+            XeroInvoiceStatus::Paid => InvoiceStatus::Paid,
+            XeroInvoiceStatus::Deleted => InvoiceStatus::Deleted,
+            XeroInvoiceStatus::Voided => InvoiceStatus::Voided,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XeroContact {
+    #[serde(rename = "ContactID")]
+    contact_id: String,
+    name: String,
+}
+
+impl From<XeroContact> for Contact {
+    fn from(value: XeroContact) -> Self {
+        Self {
+            contact_id: value.contact_id,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct PaymentRequestObject {
+    invoice: PaymentRequestObjectInvoice,
+    account: PaymentRequestObjectAccount,
+    date: chrono::NaiveDate,
+    #[serde(with = "rust_decimal::serde::float")]
+    amount: Decimal,
+    reference: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+struct PaymentRequestObjectInvoice {
+    #[serde(rename = "InvoiceID")]
+    invoice_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+struct PaymentRequestObjectAccount {
+    #[serde(rename = "Code")]
+    code: String,
+}
@@ -1,32 +1,61 @@
 #![allow(dead_code)]
 
-use std::{fmt::Display, io::stdin, ops::Div};
+use std::io::stdin;
 
 use anyhow::Result;
-use base64::{Engine, prelude::BASE64_STANDARD};
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
-use clap::Parser;
+use chrono::{Duration, NaiveDate, Utc};
+use clap::{Parser, ValueEnum};
 use regex::Regex;
-use reqwest::{
-    Client,
-    header::{AUTHORIZATION, HeaderMap, HeaderValue},
-};
-use reqwest_middleware::ClientWithMiddleware;
-use rust_decimal::{
-    Decimal,
-    prelude::{FromPrimitive, ToPrimitive},
-};
-use serde::{Deserialize, Serialize};
-use serde_json::{Value, json};
-
-use crate::http::http_client;
+
+use crate::ledger::{Invoice, InvoiceMatchResult, Ledger, PaymentRequest, xero::XeroLedger};
+use crate::matching::{MatchStrategy, find_match, find_refund_match};
+use crate::pos::{CashEvent, CashEventKind, PosSource, square::SquarePos};
+use crate::store::ReconciliationStore;
 
 mod http;
+mod ledger;
+mod matching;
+mod pos;
+mod store;
 
 #[derive(Debug, Clone, Parser)]
 struct Args {
+    /// Lower bound for the scan. Defaults to the end of the last
+    /// successfully completed scan for this location, so routine runs only
+    /// cover new activity; required the first time a location is scanned.
     #[arg(short, long)]
-    since: NaiveDate,
+    since: Option<NaiveDate>,
+
+    #[arg(long, value_enum, default_value_t = PosBackend::Square)]
+    pos: PosBackend,
+
+    #[arg(long, value_enum, default_value_t = LedgerBackend::Xero)]
+    ledger: LedgerBackend,
+
+    /// Path to the SQLite store tracking which cash events have already
+    /// been reconciled, so overlapping runs don't double-submit.
+    #[arg(long, default_value = "cashtx.sqlite3")]
+    store_path: String,
+
+    /// How long a scan's "in progress" marker is honoured before it's
+    /// considered abandoned and a new scan is allowed to proceed anyway.
+    #[arg(long, default_value_t = 60)]
+    max_scan_age_minutes: i64,
+
+    /// Safety bound on how many pages to follow when paginating the POS and
+    /// ledger APIs, so a runaway cursor/page chain can't loop forever.
+    #[arg(long, default_value_t = 50)]
+    max_pages: usize,
+
+    /// Which rapidfuzz scorer to use when comparing a cash event's
+    /// description against a ledger contact's name.
+    #[arg(long, value_enum, default_value_t = MatchStrategy::Lcs)]
+    match_strategy: MatchStrategy,
+
+    /// Minimum similarity (0.0-1.0) a contact name must score against a cash
+    /// event's description to be considered a match.
+    #[arg(long, default_value_t = 0.2)]
+    match_threshold: f64,
 
     #[clap(env = "SQUARE_SHIFT_EVENT_DESCRIPTION_EXCLUSIONS_PATTERN")]
     exclusions: String,
@@ -53,210 +82,14 @@ struct Args {
     xero_payment_account_code: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct Shift {
-    id: String,
-    state: ShiftState,
-    created_at: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-enum ShiftState {
-    Open,
-    Closed,
-    Ended,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct GetShiftsResponse {
-    cash_drawer_shifts: Vec<Shift>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct ShiftEvent {
-    event_type: ShiftEventType,
-    event_money: ShiftEventMoney,
-    description: Option<String>,
-}
-
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-enum ShiftEventType {
-    NoSale,
-    CashTenderPayment,
-    OtherTenderPayment,
-    CashTenderCancelledPayment,
-    OtherTenderCancelledPayment,
-    CashTenderRefund,
-    OtherTenderRefund,
-    PaidIn,
-    PaidOut,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct ShiftEventMoney {
-    amount: f64,
-    currency: String,
-}
-
-impl ShiftEventMoney {
-    fn as_dec(&self) -> f64 {
-        let dec = Decimal::from_f64(self.amount.div(100.0)).unwrap();
-        dec.to_f64().unwrap()
-    }
-}
-
-impl Display for ShiftEventMoney {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", Decimal::from_f64(self.amount.div(100.0)).unwrap())
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct GetShiftEventsResponse {
-    cash_drawer_shift_events: Vec<ShiftEvent>,
-}
-
-#[derive(Debug, Default, Clone, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-struct GetInvoicesResponse {
-    invoices: Vec<Invoice>,
-}
-
-#[derive(Debug, Default, Clone)]
-enum InvoiceMatchResult {
-    #[default]
-    None,
-    AlreadyPaid,
-    UnpaidSingle(Invoice),
-    UnpaidMultiple(Vec<Invoice>),
-}
-
-fn fuzzy_matches_contact(event: &ShiftEvent, contact: &Contact) -> bool {
-    let Some(desc) = event.description.as_deref() else {
-        return false;
-    };
-
-    let threshold =
-        rapidfuzz::distance::lcs_seq::normalized_similarity(desc.chars(), contact.name.chars());
-
-    threshold >= 0.2
-}
-
-impl GetInvoicesResponse {
-    fn find_match(&self, event: &ShiftEvent) -> InvoiceMatchResult {
-        let event_dec = Decimal::from_f64(event.event_money.amount)
-            .unwrap()
-            .div(Decimal::from(100))
-            .normalize()
-            .round_dp(2);
-
-        let matching_by_amount = self
-            .invoices
-            .iter()
-            .filter(|inv| inv.invoice_type == InvoiceType::AccPay)
-            .filter(|inv| fuzzy_matches_contact(event, &inv.contact))
-            .filter(|inv| {
-                let amount_due = Decimal::from_f64(inv.amount_due)
-                    .unwrap()
-                    .normalize()
-                    .round_dp(2);
-                let amount_paid = Decimal::from_f64(inv.amount_paid)
-                    .unwrap()
-                    .normalize()
-                    .round_dp(2);
-                amount_due == event_dec || amount_paid == event_dec
-            })
-            .cloned()
-            .collect::<Vec<_>>();
-
-        if matching_by_amount.is_empty() {
-            return InvoiceMatchResult::None;
-        }
-
-        let (paid, unpaid): (Vec<_>, Vec<_>) = matching_by_amount
-            .iter()
-            .partition(|inv| inv.amount_paid > 0.0);
-
-        if !paid.is_empty() {
-            return InvoiceMatchResult::AlreadyPaid;
-        }
-
-        let unpaid = unpaid.into_iter().cloned().collect::<Vec<_>>();
-
-        match unpaid.len() {
-            0 => InvoiceMatchResult::None,
-            1 => InvoiceMatchResult::UnpaidSingle(unpaid[0].clone()),
-            _ => InvoiceMatchResult::UnpaidMultiple(unpaid),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-struct Invoice {
-    #[serde(rename = "Type")]
-    invoice_type: InvoiceType,
-    #[serde(rename = "InvoiceID")]
-    invoice_id: String,
-    invoice_number: String,
-    amount_due: f64,
-    amount_paid: f64,
-    contact: Contact,
-    #[serde(rename = "DateString")]
-    date: NaiveDateTime,
-    #[serde(rename = "DueDateString")]
-    due_date: NaiveDateTime,
-    status: InvoiceStatus,
-}
-
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "UPPERCASE")]
-enum InvoiceType {
-    AccPay,
-    AccRec,
-}
-
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-enum InvoiceStatus {
-    Draft,
-    Submitted,
-    Authorised,
-    Paid,
-    Deleted,
-    Voided,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-struct Contact {
-    #[serde(rename = "ContactID")]
-    contact_id: String,
-    name: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "PascalCase")]
-struct PaymentRequestObject {
-    invoice: PaymentRequestObjectInvoice,
-    account: PaymentRequestObjectAccount,
-    date: NaiveDate,
-    amount: f64,
-    reference: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct PaymentRequestObjectInvoice {
-    #[serde(rename = "InvoiceID")]
-    invoice_id: String,
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PosBackend {
+    Square,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct PaymentRequestObjectAccount {
-    #[serde(rename = "Code")]
-    code: String,
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum LedgerBackend {
+    Xero,
 }
 
 #[tokio::main]
@@ -264,8 +97,26 @@ pub async fn main() -> Result<()> {
     dotenvy::dotenv()?;
     let args = Args::parse();
 
-    let square = square_client(&args);
-    let xero = xero_client(&args).await;
+    let pos_source: Box<dyn PosSource> = match args.pos {
+        PosBackend::Square => Box::new(SquarePos::new(
+            args.square_location_id.clone(),
+            &args.square_access_token,
+            args.max_pages,
+        )),
+    };
+
+    let ledger: Box<dyn Ledger> = match args.ledger {
+        LedgerBackend::Xero => Box::new(
+            XeroLedger::new(
+                &args.xero_client_id,
+                &args.xero_client_secret,
+                &args.xero_tenant_id,
+                args.xero_payment_account_code.clone(),
+                args.max_pages,
+            )
+            .await?,
+        ),
+    };
 
     println!();
 
@@ -273,144 +124,215 @@ pub async fn main() -> Result<()> {
         println!("   Excluding shift events matching: {}", args.exclusions);
     }
 
-    let invoices: GetInvoicesResponse = match xero
-        .get("https://api.xero.com/api.xro/2.0/Invoices")
-        .query(&[("Statuses", "AUTHORISED,PAID"), ("pageSize", "1000")])
-        .send()
-        .await?
-        .error_for_status()
-    {
-        Ok(res) => res.json().await?,
+    let store = ReconciliationStore::open(&args.store_path)?;
+    store.begin_scan(
+        &args.square_location_id,
+        Duration::minutes(args.max_scan_age_minutes),
+    )?;
+
+    let since = match args.since {
+        Some(since) => since,
+        None => match store.last_scanned_at(&args.square_location_id)? {
+            Some(last_scanned_at) => last_scanned_at.date_naive(),
+            None => {
+                store.release_scan_lock(&args.square_location_id)?;
+                eprintln!(
+                    "   No prior scan recorded for {}; pass --since for the first run",
+                    args.square_location_id
+                );
+                return Ok(());
+            }
+        },
+    };
+
+    let scan_start = Utc::now();
+
+    let invoices = match ledger.candidate_invoices().await {
+        Ok(invoices) => invoices,
         Err(err) => {
             eprintln!("   Failed to get invoices: {err}");
+            store.release_scan_lock(&args.square_location_id)?;
             return Ok(());
         }
     };
 
-    println!("   Retrieved {} invoices", invoices.invoices.len());
-    if invoices.invoices.len() == 1000 {
-        println!("   Warning: invoice count matches page size; run again after this");
-    }
+    println!("   Retrieved {} invoices", invoices.len());
 
-    let shifts: GetShiftsResponse = match square
-        .get("https://connect.squareup.com/v2/cash-drawers/shifts")
-        .query(&[
-            ("location_id", &args.square_location_id),
-            (
-                "begin_time",
-                &args.since.format("%Y-%m-%dT00:00:00.0000").to_string(),
-            ),
-        ])
-        .send()
-        .await?
-        .error_for_status()
-    {
-        Ok(res) => res.json().await?,
+    let events = match pos_source.fetch_cash_events(since).await {
+        Ok(events) => events,
         Err(err) => {
             eprintln!("Failed to get shifts: {err}");
+            store.release_scan_lock(&args.square_location_id)?;
             return Ok(());
         }
     };
 
     let mut unmatched = Vec::new();
-    let mut matched: Vec<(Shift, ShiftEvent, Invoice)> = Vec::new();
+    let mut matched: Vec<(CashEvent, Invoice)> = Vec::new();
     let mut already_paid = Vec::new();
+    let mut already_reconciled = Vec::new();
+    let mut refund_events = Vec::new();
 
-    println!(
-        "   Processing {} shifts...",
-        shifts.cash_drawer_shifts.len()
-    );
+    println!("   Processing {} cash events...", events.len());
 
-    for shift in shifts.cash_drawer_shifts {
-        if shift.state != ShiftState::Closed {
+    for event in events {
+        if !args.exclusions.is_empty() && is_excluded(&event, &args.exclusions) {
             continue;
         }
 
-        let events: GetShiftEventsResponse = square
-            .get(format!(
-                "https://connect.squareup.com/v2/cash-drawers/shifts/{}/events",
-                shift.id
-            ))
-            .query(&[("location_id", &args.square_location_id)])
-            .send()
-            .await?
-            .json()
-            .await?;
-
-        for event in events.cash_drawer_shift_events {
-            if event.event_type != ShiftEventType::PaidOut {
-                continue;
-            }
+        if store.is_reconciled(&event.id)? {
+            already_reconciled.push(event);
+            continue;
+        }
 
-            if !args.exclusions.is_empty() && is_excluded(&event, &args.exclusions) {
-                continue;
-            }
+        if event.kind.is_refund() {
+            refund_events.push(event);
+            continue;
+        }
 
-            match invoices.find_match(&event) {
-                InvoiceMatchResult::None => {
-                    unmatched.push((shift.clone(), event));
-                }
-                InvoiceMatchResult::AlreadyPaid => {
-                    already_paid.push(event);
-                }
-                InvoiceMatchResult::UnpaidSingle(invoice) => {
-                    matched.push((shift.clone(), event, invoice));
-                }
-                InvoiceMatchResult::UnpaidMultiple(invoices) => {
-                    match prompt_invoice_match(&shift, &event, &invoices) {
-                        Some(inv) => {
-                            matched.push((shift.clone(), event, inv));
-                        }
-                        None => {
-                            unmatched.push((shift.clone(), event));
-                        }
+        if event.kind != CashEventKind::PaidOut {
+            continue;
+        }
+
+        match find_match(
+            ledger.as_ref(),
+            &invoices,
+            &event,
+            args.match_strategy,
+            args.match_threshold,
+        ) {
+            InvoiceMatchResult::None => {
+                unmatched.push(event);
+            }
+            InvoiceMatchResult::AlreadyPaid => {
+                already_paid.push(event);
+            }
+            InvoiceMatchResult::UnpaidSingle(invoice) => {
+                matched.push((event, invoice));
+            }
+            InvoiceMatchResult::UnpaidMultiple(invoices) => {
+                match prompt_invoice_match(&event, &invoices) {
+                    Some(inv) => {
+                        matched.push((event, inv));
+                    }
+                    None => {
+                        unmatched.push(event);
                     }
                 }
             }
+            InvoiceMatchResult::RefundForReconciledPayment(_) | InvoiceMatchResult::RefundForInvoice(_) => {
+                unreachable!("find_match never returns a refund match")
+            }
         }
     }
 
-    print_progress(&matched, &already_paid, &unmatched);
+    // Payments this run already matched, plus payments reconciled in earlier
+    // runs (resolved against the invoices just fetched), so a refund that
+    // posts in a later `--since` window than its original payment can still
+    // be traced back to it.
+    let mut reconciled_payments = matched
+        .iter()
+        .map(|(e, i)| (e.money.as_dec(), i.clone()))
+        .collect::<Vec<_>>();
+    reconciled_payments.extend(store.reconciled_payments(&invoices)?);
+
+    let mut refunds: Vec<(CashEvent, Invoice)> = Vec::new();
+    let mut unmatched_refunds = Vec::new();
+
+    for event in refund_events {
+        match find_refund_match(
+            &invoices,
+            &reconciled_payments,
+            &event,
+            args.match_strategy,
+            args.match_threshold,
+        ) {
+            InvoiceMatchResult::RefundForReconciledPayment(invoice)
+            | InvoiceMatchResult::RefundForInvoice(invoice) => {
+                refunds.push((event, invoice));
+            }
+            _ => unmatched_refunds.push(event),
+        }
+    }
+
+    print_progress(
+        &matched,
+        &already_paid,
+        &unmatched,
+        &refunds,
+        &unmatched_refunds,
+        &already_reconciled,
+    );
 
-    let payment_objects = matched
+    let payment_requests = matched
         .iter()
-        .map(|(s, e, i)| PaymentRequestObject {
-            invoice: PaymentRequestObjectInvoice {
-                invoice_id: i.invoice_id.clone(),
-            },
-            account: PaymentRequestObjectAccount {
-                code: args.xero_payment_account_code.clone(),
-            },
-            date: s.created_at.date_naive(),
-            amount: e.event_money.as_dec(),
+        .map(|(e, i)| PaymentRequest {
+            invoice_id: i.invoice_id.clone(),
+            date: e.occurred_at.date_naive(),
+            amount: e.money.as_dec(),
             reference: "Auto-reconciled using cashtx tool".to_string(),
         })
+        .chain(refunds.iter().map(|(e, i)| PaymentRequest {
+            invoice_id: i.invoice_id.clone(),
+            date: e.occurred_at.date_naive(),
+            amount: -e.money.as_dec(),
+            reference: "Refund auto-reconciled using cashtx tool".to_string(),
+        }))
         .collect::<Vec<_>>();
 
-    if payment_objects.is_empty() {
+    if payment_requests.is_empty() {
         println!("   Done, no payments needed to be submitted");
+        store.complete_scan(&args.square_location_id, scan_start)?;
         return Ok(());
     }
 
-    match xero
-        .put("https://api.xero.com/api.xro/2.0/Payments")
-        .json(&json!({
-            "Payments": payment_objects,
-        }))
-        .send()
-        .await?
-        .error_for_status()
-    {
-        Ok(_) => {
+    let mut event_ids = matched
+        .iter()
+        .map(|(e, _)| e.id.as_str())
+        .chain(refunds.iter().map(|(e, _)| e.id.as_str()))
+        .collect::<Vec<_>>();
+    event_ids.sort_unstable();
+    let idempotency_key = event_ids.join(",");
+
+    match ledger.submit_payments(&idempotency_key, &payment_requests).await {
+        Ok(()) => {
             println!("   Payments submitted successfully");
+
+            for (e, i) in &matched {
+                if let Err(err) =
+                    store.insert_or_update_payment(&e.id, &i.invoice_id, e.money.as_dec())
+                {
+                    eprintln!(
+                        "   Warning: payment for event {} (invoice {}) succeeded in the ledger \
+                         but failed to record locally, it may be resubmitted next run: {err}",
+                        e.id, i.invoice_id
+                    );
+                }
+            }
+            for (e, i) in &refunds {
+                if let Err(err) =
+                    store.insert_or_update_payment(&e.id, &i.invoice_id, -e.money.as_dec())
+                {
+                    eprintln!(
+                        "   Warning: refund for event {} (invoice {}) succeeded in the ledger \
+                         but failed to record locally, it may be resubmitted next run: {err}",
+                        e.id, i.invoice_id
+                    );
+                }
+            }
+
+            store.complete_scan(&args.square_location_id, scan_start)?;
+        }
+        Err(err) => {
+            eprintln!("   Failed to submit payments: {err}");
+            store.release_scan_lock(&args.square_location_id)?;
         }
-        Err(err) => eprintln!("   Failed to submit payments: {err}"),
     }
 
     Ok(())
 }
 
-fn is_excluded(event: &ShiftEvent, pattern: &str) -> bool {
+fn is_excluded(event: &CashEvent, pattern: &str) -> bool {
     // Shouldn't be compiling regex here but I have no respect for my CPU so fuck it
     let re = Regex::new(pattern).expect("invalid exclusion pattern");
     re.is_match(
@@ -424,21 +346,38 @@ fn is_excluded(event: &ShiftEvent, pattern: &str) -> bool {
 }
 
 fn print_progress(
-    matched: &[(Shift, ShiftEvent, Invoice)],
-    already_paid: &[ShiftEvent],
-    unmatched: &[(Shift, ShiftEvent)],
+    matched: &[(CashEvent, Invoice)],
+    already_paid: &[CashEvent],
+    unmatched: &[CashEvent],
+    refunds: &[(CashEvent, Invoice)],
+    unmatched_refunds: &[CashEvent],
+    already_reconciled: &[CashEvent],
 ) {
     println!();
 
+    println!("   Already reconciled (skipped):");
+    for e in already_reconciled {
+        println!(
+            "     {} £{} {}",
+            e.description
+                .as_deref()
+                .map(|s| s.trim())
+                .unwrap_or("(no description)"),
+            e.money,
+            e.occurred_at.format("%Y-%m-%d")
+        );
+    }
+    println!();
+
     println!("   Matched transactions:");
-    for (_, e, i) in matched {
+    for (e, i) in matched {
         println!(
             "     {} £{} => {} £{}",
             e.description
                 .as_deref()
                 .map(|s| s.trim())
                 .unwrap_or("(no description)"),
-            e.event_money,
+            e.money,
             i.contact.name,
             i.amount_due
         );
@@ -453,37 +392,62 @@ fn print_progress(
                 .as_deref()
                 .map(|s| s.trim())
                 .unwrap_or("(no description)"),
-            e.event_money,
+            e.money,
         );
     }
     println!();
 
     println!("   Unmatched transactions:");
-    for (s, e) in unmatched {
+    for e in unmatched {
         println!(
             "     {} £{} {}",
             e.description
                 .as_deref()
                 .map(|s| s.trim())
                 .unwrap_or("(no description)"),
-            e.event_money,
-            s.created_at.format("%Y-%m-%d")
+            e.money,
+            e.occurred_at.format("%Y-%m-%d")
+        );
+    }
+    println!();
+
+    println!("   Matched refunds (reversing original order):");
+    for (e, i) in refunds {
+        println!(
+            "     {} £{} => {} £{}",
+            e.description
+                .as_deref()
+                .map(|s| s.trim())
+                .unwrap_or("(no description)"),
+            e.money,
+            i.contact.name,
+            i.amount_due
+        );
+    }
+    println!();
+
+    println!("   Unmatched refunds:");
+    for e in unmatched_refunds {
+        println!(
+            "     {} £{} {}",
+            e.description
+                .as_deref()
+                .map(|s| s.trim())
+                .unwrap_or("(no description)"),
+            e.money,
+            e.occurred_at.format("%Y-%m-%d")
         );
     }
     println!();
 }
 
-fn prompt_invoice_match(
-    shift: &Shift,
-    event: &ShiftEvent,
-    invoices: &[Invoice],
-) -> Option<Invoice> {
+fn prompt_invoice_match(event: &CashEvent, invoices: &[Invoice]) -> Option<Invoice> {
     println!();
     println!(
         "   Pick invoice for cash event: {} {} £{}",
-        shift.created_at.format("%Y-%m-%d"),
+        event.occurred_at.format("%Y-%m-%d"),
         event.description.as_deref().unwrap_or("(no description)"),
-        event.event_money
+        event.money
     );
     println!();
 
@@ -512,87 +476,3 @@ fn prompt_invoice_match(
 
     invoices.get(chosen_int).cloned()
 }
-
-fn square_client(args: &Args) -> ClientWithMiddleware {
-    let mut headers = HeaderMap::new();
-
-    let mut auth_value =
-        HeaderValue::from_str(&format!("Bearer {}", args.square_access_token)).unwrap();
-    auth_value.set_sensitive(true);
-    headers.insert(AUTHORIZATION, auth_value);
-
-    headers.insert("Square-Version", "2025-10-16".parse().unwrap());
-    headers.insert("Content-Type", "application/json".parse().unwrap());
-
-    http_client(headers)
-}
-
-/// https://api.xero.com/api.xro/2.0/Invoices?Statuses=AUTHORISED&where=Type%3D%3D%22ACCPAY%22%20AND%20AmountDue%3D60.38
-async fn xero_client(args: &Args) -> ClientWithMiddleware {
-    let mut headers = HeaderMap::new();
-
-    let access_token = get_xero_access_token(args).await.unwrap();
-
-    let mut auth_value = HeaderValue::from_str(&format!("Bearer {}", access_token)).unwrap();
-    auth_value.set_sensitive(true);
-
-    headers.insert(AUTHORIZATION, auth_value);
-    headers.insert("Accept", "application/json".parse().unwrap());
-    headers.insert("Xero-Tenant-Id", args.xero_tenant_id.parse().unwrap());
-
-    http_client(headers)
-}
-
-async fn get_xero_access_token(args: &Args) -> Result<String> {
-    let mut headers = HeaderMap::new();
-
-    let mut auth_value = HeaderValue::from_str(&format!(
-        "Basic {}",
-        BASE64_STANDARD.encode(format!(
-            "{}:{}",
-            args.xero_client_id, args.xero_client_secret
-        ))
-    ))
-    .unwrap();
-
-    auth_value.set_sensitive(true);
-    headers.insert(reqwest::header::AUTHORIZATION, auth_value);
-
-    let scopes = [
-        "accounting.transactions",
-        "accounting.transactions.read",
-        "accounting.reports.read",
-        "accounting.reports.tenninetynine.read",
-        "accounting.budgets.read",
-        "accounting.journals.read",
-        "accounting.settings",
-        "accounting.settings.read",
-        "accounting.contacts",
-        "accounting.attachments",
-        "accounting.contacts.read",
-        "accounting.attachments.read",
-    ]
-    .join(" ");
-
-    headers.insert(
-        "Content-Type",
-        "application/x-www-form-urlencoded".parse().unwrap(),
-    );
-
-    let client = Client::builder().default_headers(headers).build()?;
-
-    let response: Value = client
-        .post("https://identity.xero.com/connect/token")
-        .form(&[("grant_type", "client_credentials"), ("scope", &scopes)])
-        .send()
-        .await?
-        .json()
-        .await?;
-
-    Ok(response
-        .pointer("/access_token")
-        .unwrap()
-        .as_str()
-        .unwrap()
-        .to_string())
-}
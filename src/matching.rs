@@ -0,0 +1,210 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use clap::ValueEnum;
+use rapidfuzz::distance::{jaro_winkler, lcs_seq};
+use rapidfuzz::fuzz;
+use rust_decimal::Decimal;
+
+use crate::ledger::{Contact, Invoice, InvoiceMatchResult, InvoiceType, Ledger};
+use crate::pos::CashEvent;
+
+/// Which rapidfuzz scorer to compare a cash event's free-text description
+/// against a ledger contact's name with. All strategies are normalized to a
+/// common 0.0-1.0 similarity scale, so `--match-threshold` means the same
+/// thing regardless of which one is selected.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum MatchStrategy {
+    /// Longest common subsequence; cheap, but sensitive to word order.
+    Lcs,
+    /// Character-level edit similarity with a bonus for matching prefixes.
+    JaroWinkler,
+    /// Token-set overlap; tolerant of extra or missing words.
+    TokenSetRatio,
+    /// Token-sort overlap; tolerant of reordered words, e.g. "Smith John"
+    /// against "John Smith Ltd".
+    TokenSortRatio,
+}
+
+impl MatchStrategy {
+    fn similarity(self, a: &str, b: &str) -> f64 {
+        match self {
+            MatchStrategy::Lcs => lcs_seq::normalized_similarity(a.chars(), b.chars()),
+            MatchStrategy::JaroWinkler => {
+                jaro_winkler::normalized_similarity(a.chars(), b.chars())
+            }
+            MatchStrategy::TokenSetRatio => token_set_ratio(a, b),
+            MatchStrategy::TokenSortRatio => token_sort_ratio(a, b),
+        }
+    }
+}
+
+/// Sorts a string's whitespace-separated tokens so word order doesn't
+/// affect the comparison, e.g. "Smith John" and "John Smith" both become
+/// "John Smith".
+fn sorted_tokens(s: &str) -> String {
+    let mut tokens = s.split_whitespace().collect::<Vec<_>>();
+    tokens.sort_unstable();
+    tokens.join(" ")
+}
+
+/// `rapidfuzz::fuzz` only exposes a plain `ratio`, so the token-sort and
+/// token-set scorers below are built on top of it rather than using a
+/// dedicated rapidfuzz function, mirroring the classic fuzzywuzzy
+/// algorithms: reorder tokens (sort) or compare tokens as sets
+/// (intersection vs. each side's leftovers) before scoring.
+fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    fuzz::ratio(sorted_tokens(a).chars(), sorted_tokens(b).chars())
+}
+
+fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let tokens_a = a.split_whitespace().collect::<BTreeSet<_>>();
+    let tokens_b = b.split_whitespace().collect::<BTreeSet<_>>();
+
+    let intersection = tokens_a
+        .intersection(&tokens_b)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let only_a = tokens_a
+        .difference(&tokens_b)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let only_b = tokens_b
+        .difference(&tokens_a)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let combined_a = [intersection.as_str(), only_a.as_str()]
+        .join(" ")
+        .trim()
+        .to_string();
+    let combined_b = [intersection.as_str(), only_b.as_str()]
+        .join(" ")
+        .trim()
+        .to_string();
+
+    [
+        fuzz::ratio(intersection.chars(), combined_a.chars()),
+        fuzz::ratio(intersection.chars(), combined_b.chars()),
+        fuzz::ratio(combined_a.chars(), combined_b.chars()),
+    ]
+    .into_iter()
+    .fold(0.0_f64, f64::max)
+}
+
+/// Normalizes a decimal amount to two places so equality comparisons
+/// aren't thrown off by differing scales (e.g. `60` vs `60.00`).
+fn rounded(amount: Decimal) -> Decimal {
+    amount.normalize().round_dp(2)
+}
+
+/// Similarity between a cash event's description and a contact's name,
+/// or `0.0` if the event has no description to compare against.
+pub fn contact_similarity(event: &CashEvent, contact: &Contact, strategy: MatchStrategy) -> f64 {
+    let Some(desc) = event.description.as_deref() else {
+        return 0.0;
+    };
+
+    strategy.similarity(desc, &contact.name)
+}
+
+pub fn fuzzy_matches_contact(
+    event: &CashEvent,
+    contact: &Contact,
+    strategy: MatchStrategy,
+    threshold: f64,
+) -> bool {
+    contact_similarity(event, contact, strategy) >= threshold
+}
+
+/// Ranks invoices by contact-name similarity against `event`, most similar
+/// first, so a caller choosing between several amount-matched candidates
+/// (e.g. [`prompt_invoice_match`](crate::prompt_invoice_match)) sees the
+/// likeliest one first.
+fn rank_by_similarity(invoices: &mut [Invoice], event: &CashEvent, strategy: MatchStrategy) {
+    invoices.sort_by(|a, b| {
+        contact_similarity(event, &b.contact, strategy)
+            .partial_cmp(&contact_similarity(event, &a.contact, strategy))
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+pub fn find_match(
+    ledger: &dyn Ledger,
+    invoices: &[Invoice],
+    event: &CashEvent,
+    strategy: MatchStrategy,
+    threshold: f64,
+) -> InvoiceMatchResult {
+    let event_dec = rounded(event.money.as_dec());
+
+    let matching_by_amount = invoices
+        .iter()
+        .filter(|inv| inv.invoice_type == InvoiceType::AccPay)
+        .filter(|inv| fuzzy_matches_contact(event, &inv.contact, strategy, threshold))
+        .filter(|inv| rounded(inv.amount_due) == event_dec || rounded(inv.amount_paid) == event_dec)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if matching_by_amount.is_empty() {
+        return InvoiceMatchResult::None;
+    }
+
+    let (paid, unpaid): (Vec<_>, Vec<_>) = matching_by_amount
+        .iter()
+        .partition(|inv| ledger.is_already_paid(inv));
+
+    if !paid.is_empty() {
+        return InvoiceMatchResult::AlreadyPaid;
+    }
+
+    let mut unpaid = unpaid.into_iter().cloned().collect::<Vec<_>>();
+
+    match unpaid.len() {
+        0 => InvoiceMatchResult::None,
+        1 => InvoiceMatchResult::UnpaidSingle(unpaid[0].clone()),
+        _ => {
+            rank_by_similarity(&mut unpaid, event, strategy);
+            InvoiceMatchResult::UnpaidMultiple(unpaid)
+        }
+    }
+}
+
+/// Matches a refund/cancellation event against either a payment already
+/// reconciled (in this run, or via [`crate::store::ReconciliationStore`] in an
+/// earlier one), or failing that, a receivable invoice for the same contact
+/// whose amount due or amount paid equals the refund.
+pub fn find_refund_match(
+    invoices: &[Invoice],
+    reconciled_payments: &[(Decimal, Invoice)],
+    event: &CashEvent,
+    strategy: MatchStrategy,
+    threshold: f64,
+) -> InvoiceMatchResult {
+    let event_dec = rounded(event.money.as_dec());
+
+    let already_reconciled = reconciled_payments.iter().find(|(paid_amount, invoice)| {
+        fuzzy_matches_contact(event, &invoice.contact, strategy, threshold)
+            && rounded(*paid_amount) == event_dec
+    });
+
+    if let Some((_, invoice)) = already_reconciled {
+        return InvoiceMatchResult::RefundForReconciledPayment(invoice.clone());
+    }
+
+    let matching_invoice = invoices
+        .iter()
+        .filter(|inv| inv.invoice_type == InvoiceType::AccRec)
+        .find(|inv| {
+            fuzzy_matches_contact(event, &inv.contact, strategy, threshold)
+                && (rounded(inv.amount_due) == event_dec || rounded(inv.amount_paid) == event_dec)
+        });
+
+    match matching_invoice {
+        Some(invoice) => InvoiceMatchResult::RefundForInvoice(invoice.clone()),
+        None => InvoiceMatchResult::None,
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::{self, Display};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::{Decimal, prelude::FromPrimitive};
+use serde::{
+    Deserialize, Deserializer,
+    de::{self, Visitor},
+};
+
+pub mod square;
+
+/// A point-of-sale backend capable of reporting cash events (payments,
+/// refunds, cancellations, ...) recorded since a given date.
+///
+/// Implementations are responsible for normalizing whatever shape their
+/// upstream API uses into [`CashEvent`] so the reconciliation loop never has
+/// to know which POS produced the data.
+#[async_trait]
+pub trait PosSource {
+    async fn fetch_cash_events(&self, since: NaiveDate) -> Result<Vec<CashEvent>>;
+}
+
+/// A single cash-drawer event, normalized away from any particular POS's
+/// wire format.
+#[derive(Debug, Clone)]
+pub struct CashEvent {
+    /// Stable identifier from the POS backend, used as the idempotency key
+    /// when persisting reconciliation state.
+    pub id: String,
+    pub kind: CashEventKind,
+    pub money: Money,
+    pub description: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CashEventKind {
+    NoSale,
+    CashTenderPayment,
+    OtherTenderPayment,
+    CashTenderCancelledPayment,
+    OtherTenderCancelledPayment,
+    CashTenderRefund,
+    OtherTenderRefund,
+    PaidIn,
+    PaidOut,
+}
+
+impl CashEventKind {
+    /// Whether this event reverses money that a prior event paid out, and
+    /// should therefore be reconciled as a refund rather than a payment.
+    pub fn is_refund(&self) -> bool {
+        matches!(
+            self,
+            CashEventKind::CashTenderRefund
+                | CashEventKind::OtherTenderRefund
+                | CashEventKind::CashTenderCancelledPayment
+                | CashEventKind::OtherTenderCancelledPayment
+        )
+    }
+}
+
+/// An amount of money in integer minor units (e.g. pence), as Square
+/// reports it on shift events.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Money {
+    #[serde(deserialize_with = "deserialize_minor_units")]
+    pub amount: i64,
+    pub currency: String,
+}
+
+impl Money {
+    pub fn as_dec(&self) -> Decimal {
+        Decimal::from_i64(self.amount).unwrap() / Decimal::from(100)
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_dec())
+    }
+}
+
+/// Square returns minor-unit amounts as JSON integers, but accepts
+/// string-encoded integers too; accept either so a wire format change on
+/// their end doesn't fail deserialization.
+fn deserialize_minor_units<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct MinorUnitsVisitor;
+
+    impl<'de> Visitor<'de> for MinorUnitsVisitor {
+        type Value = i64;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("an integer or string-encoded integer number of minor currency units")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            i64::try_from(v).map_err(de::Error::custom)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            v.parse().map_err(de::Error::custom)
+        }
+    }
+
+    deserializer.deserialize_any(MinorUnitsVisitor)
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+
+use crate::http::http_client;
+
+use super::{CashEvent, CashEventKind, Money, PosSource};
+
+pub struct SquarePos {
+    client: ClientWithMiddleware,
+    location_id: String,
+    max_pages: usize,
+}
+
+impl SquarePos {
+    pub fn new(location_id: String, access_token: &str, max_pages: usize) -> Self {
+        let mut headers = HeaderMap::new();
+
+        let mut auth_value = HeaderValue::from_str(&format!("Bearer {access_token}")).unwrap();
+        auth_value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, auth_value);
+
+        headers.insert("Square-Version", "2025-10-16".parse().unwrap());
+        headers.insert("Content-Type", "application/json".parse().unwrap());
+
+        Self {
+            client: http_client(headers),
+            location_id,
+            max_pages,
+        }
+    }
+
+    async fn fetch_shifts(&self, since: NaiveDate) -> Result<Vec<Shift>> {
+        let mut shifts = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        for page in 1..=self.max_pages {
+            let mut query = vec![
+                ("location_id", self.location_id.clone()),
+                (
+                    "begin_time",
+                    since.format("%Y-%m-%dT00:00:00.0000").to_string(),
+                ),
+            ];
+            if let Some(cursor) = &cursor {
+                query.push(("cursor", cursor.clone()));
+            }
+
+            let response: GetShiftsResponse = self
+                .client
+                .get("https://connect.squareup.com/v2/cash-drawers/shifts")
+                .query(&query)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            shifts.extend(response.cash_drawer_shifts);
+
+            match response.cursor {
+                Some(next) if !next.is_empty() => cursor = Some(next),
+                _ => return Ok(shifts),
+            }
+
+            if page == self.max_pages {
+                println!(
+                    "   Warning: reached --max-pages ({}) while fetching shifts; results may be incomplete",
+                    self.max_pages
+                );
+            }
+        }
+
+        Ok(shifts)
+    }
+
+    async fn fetch_shift_events(&self, shift_id: &str) -> Result<Vec<ShiftEvent>> {
+        let mut shift_events = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        for page in 1..=self.max_pages {
+            let mut query = vec![("location_id", self.location_id.clone())];
+            if let Some(cursor) = &cursor {
+                query.push(("cursor", cursor.clone()));
+            }
+
+            let response: GetShiftEventsResponse = self
+                .client
+                .get(format!(
+                    "https://connect.squareup.com/v2/cash-drawers/shifts/{shift_id}/events"
+                ))
+                .query(&query)
+                .send()
+                .await?
+                .json()
+                .await?;
+
+            shift_events.extend(response.cash_drawer_shift_events);
+
+            match response.cursor {
+                Some(next) if !next.is_empty() => cursor = Some(next),
+                _ => return Ok(shift_events),
+            }
+
+            if page == self.max_pages {
+                println!(
+                    "   Warning: reached --max-pages ({}) while fetching events for shift {shift_id}; results may be incomplete",
+                    self.max_pages
+                );
+            }
+        }
+
+        Ok(shift_events)
+    }
+}
+
+#[async_trait]
+impl PosSource for SquarePos {
+    async fn fetch_cash_events(&self, since: NaiveDate) -> Result<Vec<CashEvent>> {
+        let shifts = self.fetch_shifts(since).await?;
+
+        let mut events = Vec::new();
+
+        for shift in shifts {
+            if shift.state != ShiftState::Closed {
+                continue;
+            }
+
+            let shift_events = self.fetch_shift_events(&shift.id).await?;
+
+            events.extend(shift_events.into_iter().map(|event| CashEvent {
+                id: event.id,
+                kind: event.event_type.into(),
+                money: event.event_money,
+                description: event.description,
+                occurred_at: shift.created_at,
+            }));
+        }
+
+        Ok(events)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Shift {
+    id: String,
+    state: ShiftState,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum ShiftState {
+    Open,
+    Closed,
+    Ended,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GetShiftsResponse {
+    cash_drawer_shifts: Vec<Shift>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ShiftEvent {
+    id: String,
+    event_type: ShiftEventType,
+    event_money: Money,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum ShiftEventType {
+    NoSale,
+    CashTenderPayment,
+    OtherTenderPayment,
+    CashTenderCancelledPayment,
+    OtherTenderCancelledPayment,
+    CashTenderRefund,
+    OtherTenderRefund,
+    PaidIn,
+    PaidOut,
+}
+
+impl From<ShiftEventType> for CashEventKind {
+    fn from(value: ShiftEventType) -> Self {
+        match value {
+            ShiftEventType::NoSale => CashEventKind::NoSale,
+            ShiftEventType::CashTenderPayment => CashEventKind::CashTenderPayment,
+            ShiftEventType::OtherTenderPayment => CashEventKind::OtherTenderPayment,
+            ShiftEventType::CashTenderCancelledPayment => {
+                CashEventKind::CashTenderCancelledPayment
+            }
+            ShiftEventType::OtherTenderCancelledPayment => {
+                CashEventKind::OtherTenderCancelledPayment
+            }
+            ShiftEventType::CashTenderRefund => CashEventKind::CashTenderRefund,
+            ShiftEventType::OtherTenderRefund => CashEventKind::OtherTenderRefund,
+            ShiftEventType::PaidIn => CashEventKind::PaidIn,
+            ShiftEventType::PaidOut => CashEventKind::PaidOut,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GetShiftEventsResponse {
+    cash_drawer_shift_events: Vec<ShiftEvent>,
+    cursor: Option<String>,
+}
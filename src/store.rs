@@ -0,0 +1,175 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use rust_decimal::Decimal;
+
+use crate::ledger::Invoice;
+
+/// Local record of which POS cash events have already been reconciled
+/// against the ledger, keyed by the event's stable id. Consulted before
+/// matching so re-running the tool over an overlapping `--since` window
+/// can't re-submit a payment that already went through.
+pub struct ReconciliationStore {
+    conn: Connection,
+}
+
+impl ReconciliationStore {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS reconciled_payments (
+                event_id TEXT PRIMARY KEY,
+                invoice_id TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                reconciled_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS scan_state (
+                location_id TEXT PRIMARY KEY,
+                last_scanned_at TEXT,
+                scan_started_at TEXT
+            );",
+        )?;
+
+        Ok(Self { conn })
+    }
+
+    pub fn is_reconciled(&self, event_id: &str) -> Result<bool> {
+        let found = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM reconciled_payments WHERE event_id = ?1",
+                params![event_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+
+        Ok(found)
+    }
+
+    /// Upserts a reconciled payment, keyed by the originating POS event id.
+    pub fn insert_or_update_payment(
+        &self,
+        event_id: &str,
+        invoice_id: &str,
+        amount: Decimal,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO reconciled_payments (event_id, invoice_id, amount, reconciled_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(event_id) DO UPDATE SET
+                invoice_id = excluded.invoice_id,
+                amount = excluded.amount,
+                reconciled_at = excluded.reconciled_at",
+            params![
+                event_id,
+                invoice_id,
+                amount.to_string(),
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Payments reconciled in earlier runs, resolved against the
+    /// currently-fetched `invoices` so a refund arriving in a later
+    /// `--since` window can still be matched back to the payment it
+    /// reverses, not just payments reconciled earlier in this same run.
+    pub fn reconciled_payments(&self, invoices: &[Invoice]) -> Result<Vec<(Decimal, Invoice)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT invoice_id, amount FROM reconciled_payments WHERE CAST(amount AS REAL) > 0",
+        )?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(invoice_id, amount)| {
+                let amount: Decimal = amount.parse().ok()?;
+                let invoice = invoices.iter().find(|inv| inv.invoice_id == invoice_id)?;
+                Some((amount, invoice.clone()))
+            })
+            .collect())
+    }
+
+    /// The end of the last successfully completed scan for this location,
+    /// used as the default `--since` lower bound when none is given.
+    pub fn last_scanned_at(&self, location_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let last_scanned_at: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT last_scanned_at FROM scan_state WHERE location_id = ?1",
+                params![location_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        Ok(last_scanned_at
+            .map(|s| DateTime::parse_from_rfc3339(&s))
+            .transpose()?
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    /// Marks a scan as started, refusing to proceed if a previous scan's
+    /// marker is still present and younger than `stale_after` — that scan is
+    /// assumed to still be running, and a second concurrent run would race
+    /// it to submit the same payments.
+    pub fn begin_scan(&self, location_id: &str, stale_after: Duration) -> Result<()> {
+        let now = Utc::now();
+        let stale_cutoff = (now - stale_after).to_rfc3339();
+
+        // A plain read-then-write would let two overlapping invocations both
+        // see "no marker"/"stale marker" and both proceed. Folding the
+        // staleness check into the upsert's WHERE clause makes the check and
+        // the claim a single atomic statement instead.
+        let claimed = self.conn.execute(
+            "INSERT INTO scan_state (location_id, scan_started_at)
+             VALUES (?1, ?2)
+             ON CONFLICT(location_id) DO UPDATE SET scan_started_at = excluded.scan_started_at
+             WHERE scan_state.scan_started_at IS NULL
+                OR scan_state.scan_started_at < ?3",
+            params![location_id, now.to_rfc3339(), stale_cutoff],
+        )?;
+
+        if claimed == 0 {
+            let started_at: String = self.conn.query_row(
+                "SELECT scan_started_at FROM scan_state WHERE location_id = ?1",
+                params![location_id],
+                |row| row.get(0),
+            )?;
+            bail!(
+                "a scan for location {location_id} started at {started_at} is still in progress"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Clears the in-progress marker for a location, without moving the
+    /// watermark forward.
+    pub fn release_scan_lock(&self, location_id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE scan_state SET scan_started_at = NULL WHERE location_id = ?1",
+            params![location_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Clears the in-progress marker and advances the watermark, recording
+    /// that every event up to `through` has been processed.
+    pub fn complete_scan(&self, location_id: &str, through: DateTime<Utc>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE scan_state SET scan_started_at = NULL, last_scanned_at = ?2 WHERE location_id = ?1",
+            params![location_id, through.to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+}